@@ -1,10 +1,14 @@
+use bip39::{Language, Mnemonic, MnemonicType};
 use data_encoding::HEXLOWER;
+use serde::Serialize;
 use sp_core::crypto::AccountId32;
 use sp_core::crypto::Ss58AddressFormat;
 use sp_core::crypto::Ss58Codec;
 use sp_core::Pair;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::mpsc;
 use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 use std::time::SystemTime;
@@ -27,6 +31,17 @@ fn count_letters(string: &str) -> usize {
     string.chars().filter(|c| c.is_ascii_alphabetic()).count()
 }
 
+// Whether `c` is a valid SS58 character, either as-is or, when `ignore_case`
+// is set, after flipping its ASCII case. This lets e.g. an uppercase 'I' (not
+// itself a valid SS58 character) through because it would match the lowercase
+// 'i' that --ignore-case treats it as equivalent to.
+fn is_valid_ss58_char_with_case(c: char, ignore_case: bool) -> bool {
+    is_valid_ss58_char(c)
+        || (ignore_case
+            && (is_valid_ss58_char(c.to_ascii_lowercase())
+                || is_valid_ss58_char(c.to_ascii_uppercase())))
+}
+
 #[derive(Clone)]
 struct Matcher {
     addr_type: u8,
@@ -35,18 +50,32 @@ struct Matcher {
     contains: String,
     digits: Option<usize>,
     letters: Option<usize>,
+    ignore_case: bool,
 }
 
 impl Matcher {
     fn match_(&self, candidate: &str) -> bool {
-        if !candidate.contains(&self.contains) {
-            return false;
-        }
-        if !candidate.starts_with(&self.startswith) {
-            return false;
-        }
-        if !candidate.ends_with(&self.endswith) {
-            return false;
+        if self.ignore_case {
+            let candidate_lower = candidate.to_lowercase();
+            if !candidate_lower.contains(&self.contains.to_lowercase()) {
+                return false;
+            }
+            if !candidate_lower.starts_with(&self.startswith.to_lowercase()) {
+                return false;
+            }
+            if !candidate_lower.ends_with(&self.endswith.to_lowercase()) {
+                return false;
+            }
+        } else {
+            if !candidate.contains(&self.contains) {
+                return false;
+            }
+            if !candidate.starts_with(&self.startswith) {
+                return false;
+            }
+            if !candidate.ends_with(&self.endswith) {
+                return false;
+            }
         }
         if let Some(digits) = self.digits {
             if count_digits(candidate) < digits {
@@ -63,9 +92,18 @@ impl Matcher {
 
     /// Validates the current configuration
     fn validate(&self) -> Result<(), &str> {
-        if !self.startswith.chars().all(is_valid_ss58_char)
-            || !self.endswith.chars().all(is_valid_ss58_char)
-            || !self.contains.chars().all(is_valid_ss58_char)
+        if !self
+            .startswith
+            .chars()
+            .all(|c| is_valid_ss58_char_with_case(c, self.ignore_case))
+            || !self
+                .endswith
+                .chars()
+                .all(|c| is_valid_ss58_char_with_case(c, self.ignore_case))
+            || !self
+                .contains
+                .chars()
+                .all(|c| is_valid_ss58_char_with_case(c, self.ignore_case))
         {
             return Err("Error: A provided matcher contains SS58 incompatible characters");
         }
@@ -79,7 +117,12 @@ impl Matcher {
                 );
             }
             let kusama_addr_first_chars = ['C', 'D', 'F', 'G', 'H', 'J'];
-            if self.addr_type == 2 && !kusama_addr_first_chars.contains(&first_char) {
+            let first_char_upper = first_char.to_ascii_uppercase();
+            if self.addr_type == 2
+                && !(self.ignore_case
+                    && kusama_addr_first_chars.contains(&first_char_upper))
+                && !kusama_addr_first_chars.contains(&first_char)
+            {
                 return Err("Error: Kusama address must start with one of ['C', 'D', 'F', 'G', 'H', 'J']. Adjust --startswith");
             }
             if self.addr_type == 42 && first_char != '5' {
@@ -92,28 +135,165 @@ impl Matcher {
     }
 }
 
+// The SS58 alphabet has 58 characters, 9 of which ('1'-'9') are digits and
+// the remaining 49 letters. A typical 32-byte-public-key SS58 address is
+// about this many characters long once base58-encoded with its checksum;
+// used only to turn per-constraint probabilities below into a rough
+// upfront difficulty estimate, not to validate real addresses.
+const SS58_ALPHABET_LEN: f64 = 58.0;
+const SS58_DIGIT_COUNT: f64 = 9.0;
+const SS58_ADDRESS_LEN: usize = 47;
+
+// P(X >= `successes`) for X ~ Binomial(`trials`, `p_success`).
+fn binomial_at_least_probability(trials: usize, successes: usize, p_success: f64) -> f64 {
+    if successes == 0 {
+        return 1.0;
+    }
+    if successes > trials {
+        return 0.0;
+    }
+    let mut binomial_coefficient = 1.0_f64;
+    let mut probability_mass = 0.0;
+    for i in 0..=trials {
+        let term =
+            binomial_coefficient * p_success.powi(i as i32) * (1.0 - p_success).powi((trials - i) as i32);
+        if i >= successes {
+            probability_mass += term;
+        }
+        binomial_coefficient *= (trials - i) as f64 / (i + 1) as f64;
+    }
+    probability_mass
+}
+
+// Rough probability that a random string of `len` SS58 characters contains a
+// specific `pattern_len`-character substring anywhere, treating each of the
+// `len - pattern_len + 1` starting positions as an independent trial.
+fn substring_probability(len: usize, pattern_len: usize) -> f64 {
+    if pattern_len == 0 {
+        return 1.0;
+    }
+    if pattern_len > len {
+        return 0.0;
+    }
+    let single_site_probability = SS58_ALPHABET_LEN.powi(-(pattern_len as i32));
+    let site_count = (len - pattern_len + 1) as f64;
+    1.0 - (1.0 - single_site_probability).powf(site_count)
+}
+
+// Rough probability that a uniformly random SS58 address satisfies `matcher`,
+// computed as the product of each constraint's own probability. This ignores
+// correlations between constraints (e.g. overlapping startswith/contains
+// patterns), so it is only an order-of-magnitude estimate of match difficulty.
+fn estimate_match_probability(matcher: &Matcher) -> f64 {
+    let mut probability = 1.0;
+    if !matcher.startswith.is_empty() {
+        probability *= SS58_ALPHABET_LEN.powi(-(matcher.startswith.chars().count() as i32));
+    }
+    if !matcher.endswith.is_empty() {
+        probability *= SS58_ALPHABET_LEN.powi(-(matcher.endswith.chars().count() as i32));
+    }
+    if !matcher.contains.is_empty() {
+        probability *= substring_probability(SS58_ADDRESS_LEN, matcher.contains.chars().count());
+    }
+    if let Some(digits) = matcher.digits {
+        probability *=
+            binomial_at_least_probability(SS58_ADDRESS_LEN, digits, SS58_DIGIT_COUNT / SS58_ALPHABET_LEN);
+    }
+    if let Some(letters) = matcher.letters {
+        let letter_probability = (SS58_ALPHABET_LEN - SS58_DIGIT_COUNT) / SS58_ALPHABET_LEN;
+        probability *= binomial_at_least_probability(SS58_ADDRESS_LEN, letters, letter_probability);
+    }
+    probability
+}
+
+// Formats a number of seconds as a short human-readable duration, capping
+// out at "millennia" for estimates that are effectively infeasible.
+fn format_duration_secs(seconds: f64) -> String {
+    if !seconds.is_finite() || seconds > 1e13 {
+        return String::from("millennia");
+    }
+    let seconds = seconds.max(0.0);
+    if seconds < 60.0 {
+        format!("{:.0}s", seconds)
+    } else if seconds < 3600.0 {
+        format!("{:.1}m", seconds / 60.0)
+    } else if seconds < 86400.0 {
+        format!("{:.1}h", seconds / 3600.0)
+    } else if seconds < 365.25 * 86400.0 {
+        format!("{:.1}d", seconds / 86400.0)
+    } else {
+        format!("{:.1} years", seconds / (365.25 * 86400.0))
+    }
+}
+
+// One `--grind` pattern paired with the number of matches still needed for
+// it. The counter is shared by every worker thread so whichever thread finds
+// a hit can claim it, and the main loop only tears down the pool once every
+// pattern's counter has reached zero.
+#[derive(Clone)]
+struct GrindMatch {
+    matcher: Matcher,
+    remaining: Arc<AtomicU64>,
+}
+
+// How many words and which BIP39 wordlist to generate a mnemonic phrase
+// with. `mnemonic_type` and `language` are resolved from the `--word-count`
+// and `--language` CLI options once, up front, so every worker thread reuses
+// the same validated configuration.
+#[derive(Clone)]
+struct MnemonicConfig {
+    mnemonic_type: MnemonicType,
+    language: Language,
+    passphrase: Option<String>,
+}
+
+#[derive(Clone)]
 struct Wallet {
     mnemonic_phrase: String,
-    private_key: [u8; 32],
+    has_passphrase: bool,
+    derivation_path: Option<String>,
+    private_key: Vec<u8>,
     public_key: [u8; 32],
     address: String,
 }
 
 impl Wallet {
-    fn new(addr_format: u8, with_phrase: bool) -> Wallet {
-        if with_phrase {
-            return Wallet::with_phrase(addr_format);
+    fn new(addr_format: u8, mnemonic_config: Option<&MnemonicConfig>) -> Wallet {
+        match mnemonic_config {
+            Some(mnemonic_config) => Wallet::with_phrase(addr_format, mnemonic_config),
+            None => Wallet::without_phrase(addr_format),
         }
-        Wallet::without_phrase(addr_format)
     }
 
-    fn with_phrase(addr_format: u8) -> Wallet {
-        let (pair, phrase, secret) = sp_core::sr25519::Pair::generate_with_phrase(None);
+    fn with_phrase(addr_format: u8, mnemonic_config: &MnemonicConfig) -> Wallet {
+        let mnemonic = Mnemonic::new(mnemonic_config.mnemonic_type, mnemonic_config.language);
+        let phrase = mnemonic.phrase().to_string();
+        // `sp_core::sr25519::Pair::from_phrase` parses the phrase against the
+        // English wordlist only, so it would reject every non-English
+        // `--language` we just generated. Derive the key from the mnemonic's
+        // own entropy instead, which is wordlist-independent.
+        let big_seed = substrate_bip39::seed_from_entropy(
+            mnemonic.entropy(),
+            mnemonic_config.passphrase.as_deref().unwrap_or(""),
+        )
+        .unwrap_or_else(|_error| {
+            eprintln!("Error: Could not derive a seed from the generated mnemonic");
+            std::process::exit(1);
+        });
+        let seed: [u8; 32] = big_seed[..32]
+            .try_into()
+            .expect("seed_from_entropy always returns at least 32 bytes");
+        let pair = sp_core::sr25519::Pair::from_seed_slice(&seed).unwrap_or_else(|_error| {
+            eprintln!("Error: Could not derive a keypair from the generated mnemonic's seed");
+            std::process::exit(1);
+        });
         let address = AccountId32::from(pair.public())
             .to_ss58check_with_version(Ss58AddressFormat::Custom(addr_format));
         Wallet {
             mnemonic_phrase: phrase,
-            private_key: secret,
+            has_passphrase: mnemonic_config.passphrase.is_some(),
+            derivation_path: None,
+            private_key: seed.to_vec(),
             public_key: <[u8; 32]>::from(pair.public()),
             address,
         }
@@ -126,7 +306,33 @@ impl Wallet {
             .to_ss58check_with_version(Ss58AddressFormat::Custom(addr_format));
         Wallet {
             mnemonic_phrase: phrase,
-            private_key: secret,
+            has_passphrase: false,
+            derivation_path: None,
+            private_key: secret.to_vec(),
+            public_key: <[u8; 32]>::from(pair.public()),
+            address,
+        }
+    }
+
+    // Derives a wallet from an existing mnemonic plus a `//index` hard
+    // derivation suffix, instead of generating a fresh keypair.
+    fn from_derivation(addr_format: u8, derivation: &str, passphrase: Option<&str>) -> Wallet {
+        let (pair, seed) = sp_core::sr25519::Pair::from_string_with_seed(derivation, passphrase)
+            .expect("derived secret URI must be valid");
+        let address = AccountId32::from(pair.public())
+            .to_ss58check_with_version(Ss58AddressFormat::Custom(addr_format));
+        // Hard derivation (`//index`) re-derives a fresh 32-byte seed, so this
+        // stays in the same representation as `with_phrase`/`without_phrase`'s
+        // `private_key` and can be re-imported the same way. Fall back to the
+        // raw 64-byte secret when no seed survives the derivation, which
+        // happens when the --from-phrase secret URI itself has a soft (`/`)
+        // junction.
+        let private_key = seed.unwrap_or_else(|| pair.to_raw_vec());
+        Wallet {
+            mnemonic_phrase: String::new(),
+            has_passphrase: passphrase.is_some(),
+            derivation_path: Some(String::from(derivation)),
+            private_key,
             public_key: <[u8; 32]>::from(pair.public()),
             address,
         }
@@ -135,6 +341,15 @@ impl Wallet {
     fn pretty_print(&self) {
         if !self.mnemonic_phrase.is_empty() {
             println!("Mnemonic phrase: {}", self.mnemonic_phrase);
+            if self.has_passphrase {
+                println!(
+                    "                 (this phrase alone is NOT enough to spend funds; the \
+                     BIP39 passphrase is also required)"
+                );
+            }
+        }
+        if let Some(derivation_path) = &self.derivation_path {
+            println!("Derivation path: {}", derivation_path);
         }
         println!("Private key:     {}", HEXLOWER.encode(&self.private_key));
         println!("Public key:      {}", HEXLOWER.encode(&self.public_key));
@@ -142,22 +357,78 @@ impl Wallet {
     }
 }
 
-// Generate wallets and send matching wallets to `tx` until `kill_pill`
-// is received.
+// A matching wallet serialized for `--output`/`--format json`.
+#[derive(Serialize)]
+struct WalletRecord {
+    address: String,
+    ss58_type: u8,
+    public_key: String,
+    private_key: String,
+    mnemonic_phrase: Option<String>,
+    derivation_path: Option<String>,
+}
+
+impl WalletRecord {
+    fn from_wallet(wallet: &Wallet, addr_type: u8) -> WalletRecord {
+        WalletRecord {
+            address: wallet.address.clone(),
+            ss58_type: addr_type,
+            public_key: HEXLOWER.encode(&wallet.public_key),
+            private_key: HEXLOWER.encode(&wallet.private_key),
+            mnemonic_phrase: if wallet.mnemonic_phrase.is_empty() {
+                None
+            } else {
+                Some(wallet.mnemonic_phrase.clone())
+            },
+            derivation_path: wallet.derivation_path.clone(),
+        }
+    }
+}
+
+// Search over hard-derivation suffixes of an existing mnemonic phrase
+// instead of generating fresh keypairs. Each worker thread claims a disjoint
+// slice of the index space by starting at its own `start_index` and
+// stepping by `thread_count`, so no two threads ever try the same path.
+#[derive(Clone)]
+struct FromPhraseConfig {
+    phrase: String,
+    passphrase: Option<String>,
+    start_index: u64,
+    thread_count: u64,
+}
+
+// Generate wallets and send wallets matching any still-open `grind_matches`
+// entry to `tx`, tagged with that entry's index, until `kill_pill` is
+// received.
 fn generate_matching_wallet(
-    tx: Sender<Wallet>,
+    tx: Sender<(usize, Wallet)>,
     attempt_count_tx: Sender<u64>,
     kill_pill: Receiver<()>,
-    matcher: Matcher,
+    grind_matches: Vec<GrindMatch>,
     addr_type: u8,
-    with_phrase: bool,
+    mnemonic_config: Option<MnemonicConfig>,
+    from_phrase_config: Option<FromPhraseConfig>,
 ) {
     let mut unreported_attempts: u64 = 0;
     let mut wallet: Wallet;
+    let mut derivation_index = from_phrase_config
+        .as_ref()
+        .map_or(0, |config| config.start_index);
     loop {
-        wallet = Wallet::new(addr_type, with_phrase);
-        if matcher.match_(&wallet.address) {
-            tx.send(wallet).unwrap();
+        wallet = match &from_phrase_config {
+            Some(config) => {
+                let derivation = format!("{}//{}", config.phrase, derivation_index);
+                derivation_index += config.thread_count;
+                Wallet::from_derivation(addr_type, &derivation, config.passphrase.as_deref())
+            }
+            None => Wallet::new(addr_type, mnemonic_config.as_ref()),
+        };
+        for (index, grind_match) in grind_matches.iter().enumerate() {
+            if grind_match.matcher.match_(&wallet.address)
+                && claim_remaining(&grind_match.remaining)
+            {
+                tx.send((index, wallet.clone())).unwrap();
+            }
         }
 
         let report_threshold = 1000; // Report attempt count to main thread after this many attempts
@@ -176,6 +447,21 @@ fn generate_matching_wallet(
     }
 }
 
+// Atomically decrements `remaining` if it is still above zero, returning
+// whether the caller claimed a slot. Prevents two worker threads racing to
+// report more matches for a pattern than were actually requested.
+fn claim_remaining(remaining: &AtomicU64) -> bool {
+    remaining
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |count| {
+            if count > 0 {
+                Some(count - 1)
+            } else {
+                None
+            }
+        })
+        .is_ok()
+}
+
 fn main() {
     let matches = clap::App::new("dotvanity")
         .version("0.2.7")  // DO NOT EDIT THIS LINE MANUALLY
@@ -250,6 +536,21 @@ fn main() {
                 .help("Amount of matching wallets to find")
                 .default_value("1"),
         )
+        .arg(
+            clap::Arg::with_name("grind")
+                .short("g")
+                .long("grind")
+                .value_name("STARTSWITH:ENDSWITH:COUNT")
+                .help("Grind for a vanity pattern and how many matching wallets to find for \
+                       it, e.g. `--grind 1alice:bob:2`. Either side may be left empty, e.g. \
+                       `--grind 1alice::2` or `--grind :bob:2`. May be repeated to search for \
+                       several independent patterns in the same run; the tool keeps going until \
+                       every pattern has reached its requested count. When at least one --grind \
+                       is given, --startswith, --endswith, --contains, --digits and --letters are \
+                       ignored in favor of the per-pattern startswith/endswith strings.")
+                .multiple(true)
+                .number_of_values(1),
+        )
         .arg(
             clap::Arg::with_name("verbose")
                 .short("v")
@@ -262,6 +563,79 @@ fn main() {
                 .long("mnemonic")
                 .help("Generate a mnemonic phrase for wallets")
         )
+        .arg(
+            clap::Arg::with_name("ignore case")
+                .short("i")
+                .long("ignore-case")
+                .help("Match --startswith, --endswith and --contains without regard to letter case")
+        )
+        .arg(
+            clap::Arg::with_name("word count")
+                .long("word-count")
+                .value_name("INT")
+                .help("Amount of words in the generated mnemonic phrase. One of 12, 15, 18, 21, 24. Only has an effect together with --mnemonic")
+                .default_value("12"),
+        )
+        .arg(
+            clap::Arg::with_name("language")
+                .long("language")
+                .value_name("LANGUAGE")
+                .help("BIP39 wordlist language for the generated mnemonic phrase. One of english, chinese-simplified, chinese-traditional, french, italian, japanese, korean, spanish. Only has an effect together with --mnemonic")
+                .default_value("english"),
+        )
+        .arg(
+            clap::Arg::with_name("passphrase")
+                .long("passphrase")
+                .value_name("PASSPHRASE")
+                .help("A BIP39 passphrase (the \"25th word\") to protect the mnemonic phrase with. \
+                       If given without a value, you are prompted for it interactively. Requires \
+                       --mnemonic or --from-phrase; without this option the phrase alone is sufficient to recreate the wallet.")
+                .takes_value(true)
+                .min_values(0),
+        )
+        .arg(
+            clap::Arg::with_name("from phrase")
+                .long("from-phrase")
+                .value_name("MNEMONIC")
+                .help("Instead of generating new keypairs, search for a vanity address among the \
+                       hard-derivation children of an existing mnemonic phrase, e.g. \
+                       --from-phrase \"word1 word2 ... word12\". Suffixes //0, //1, //2, ... are \
+                       tried in turn and the full derivation path is reported alongside a match \
+                       so it can be reproduced. Conflicts with --mnemonic. --passphrase, if given, \
+                       is used as this phrase's own BIP39 passphrase.")
+                .takes_value(true)
+                .conflicts_with("mnemonic"),
+        )
+        .arg(
+            clap::Arg::with_name("output")
+                .long("output")
+                .value_name("FILE")
+                .help("Write matching wallets to FILE as they are found, instead of only printing \
+                       them to stdout. With --format json (the default once --output is given), \
+                       FILE ends up holding a JSON array of wallet records (address, SS58 type, \
+                       public/private key hex, mnemonic phrase and derivation path). With \
+                       --keystore-password, FILE is instead treated as a directory and one \
+                       password-encrypted Web3 Secret Storage keystore file is written per match.")
+                .takes_value(true),
+        )
+        .arg(
+            clap::Arg::with_name("format")
+                .long("format")
+                .value_name("FORMAT")
+                .help("Output format for --output. Currently only 'json' is supported.")
+                .default_value("json"),
+        )
+        .arg(
+            clap::Arg::with_name("keystore password")
+                .long("keystore-password")
+                .value_name("PASSWORD")
+                .help("Encrypt each matching wallet's private key into a Web3 Secret Storage JSON \
+                       keystore file, written into the directory given by --output, instead of \
+                       exporting it in plaintext. If given without a value, you are prompted for \
+                       it interactively. Requires --output.")
+                .takes_value(true)
+                .min_values(0),
+        )
         .get_matches();
 
     let mnemonic = match matches.occurrences_of("mnemonic") {
@@ -276,6 +650,12 @@ fn main() {
         _ => panic!("got more than one verbose"),
     };
 
+    let ignore_case = match matches.occurrences_of("ignore case") {
+        0 => false,
+        1 => true,
+        _ => panic!("got more than one ignore-case flag"),
+    };
+
     let wallet_count_str = matches.value_of("wallet count").unwrap();
     let wallet_count: u32 = match wallet_count_str.parse() {
         Ok(wallet_count) => wallet_count,
@@ -307,6 +687,127 @@ fn main() {
         std::process::exit(1);
     }
 
+    // --word-count and --language only have an effect together with
+    // --mnemonic, so only validate them when --mnemonic is actually given;
+    // otherwise a leftover/typo'd value would abort a run that never
+    // consumes it.
+    let (mnemonic_type, language) = if mnemonic {
+        let word_count_str = matches.value_of("word count").unwrap();
+        let word_count: u32 = match word_count_str.parse() {
+            Ok(word_count) => word_count,
+            Err(_error) => {
+                eprintln!("Error: Word count is not a 32-bit unsigned integer");
+                std::process::exit(1);
+            }
+        };
+        let mnemonic_type = match word_count {
+            12 => MnemonicType::Words12,
+            15 => MnemonicType::Words15,
+            18 => MnemonicType::Words18,
+            21 => MnemonicType::Words21,
+            24 => MnemonicType::Words24,
+            _ => {
+                eprintln!("Error: Word count must be one of 12, 15, 18, 21, 24");
+                std::process::exit(1);
+            }
+        };
+
+        let language_str = matches.value_of("language").unwrap();
+        let language = match language_str.to_lowercase().as_str() {
+            "english" => Language::English,
+            "chinese-simplified" => Language::ChineseSimplified,
+            "chinese-traditional" => Language::ChineseTraditional,
+            "french" => Language::French,
+            "italian" => Language::Italian,
+            "japanese" => Language::Japanese,
+            "korean" => Language::Korean,
+            "spanish" => Language::Spanish,
+            _ => {
+                eprintln!("Error: Language must be one of english, chinese-simplified, chinese-traditional, french, italian, japanese, korean, spanish");
+                std::process::exit(1);
+            }
+        };
+        (mnemonic_type, language)
+    } else {
+        (MnemonicType::Words12, Language::English)
+    };
+    let from_phrase = matches.value_of("from phrase").map(String::from);
+
+    if matches.occurrences_of("passphrase") != 0 && !mnemonic && from_phrase.is_none() {
+        eprintln!("Error: --passphrase requires --mnemonic or --from-phrase");
+        std::process::exit(1);
+    }
+
+    let passphrase = if matches.occurrences_of("passphrase") == 0 {
+        None
+    } else {
+        match matches.value_of("passphrase") {
+            Some(value) => Some(String::from(value)),
+            None => match rpassword::prompt_password("BIP39 passphrase (the \"25th word\"): ") {
+                Ok(value) => Some(value),
+                Err(_error) => {
+                    eprintln!("Error: Failed to read passphrase from prompt");
+                    std::process::exit(1);
+                }
+            },
+        }
+    };
+
+    let mnemonic_config = if mnemonic {
+        Some(MnemonicConfig {
+            mnemonic_type,
+            language,
+            passphrase: passphrase.clone(),
+        })
+    } else {
+        None
+    };
+
+    if let Some(phrase) = &from_phrase {
+        if let Err(_error) = sp_core::sr25519::Pair::from_string(phrase, passphrase.as_deref()) {
+            eprintln!("Error: --from-phrase is not a valid mnemonic phrase or secret URI");
+            std::process::exit(1);
+        }
+    }
+
+    let output_path = matches.value_of("output").map(String::from);
+
+    let format = matches.value_of("format").unwrap();
+    if format != "json" {
+        eprintln!("Error: --format must be 'json'");
+        std::process::exit(1);
+    }
+
+    if matches.occurrences_of("keystore password") != 0 && output_path.is_none() {
+        eprintln!("Error: --keystore-password requires --output");
+        std::process::exit(1);
+    }
+    let keystore_password = if matches.occurrences_of("keystore password") == 0 {
+        None
+    } else {
+        match matches.value_of("keystore password") {
+            Some(value) => Some(String::from(value)),
+            None => match rpassword::prompt_password("Keystore password: ") {
+                Ok(value) => Some(value),
+                Err(_error) => {
+                    eprintln!("Error: Failed to read keystore password from prompt");
+                    std::process::exit(1);
+                }
+            },
+        }
+    };
+    if let Some(keystore_dir) = &output_path {
+        if keystore_password.is_some() {
+            if let Err(error) = std::fs::create_dir_all(keystore_dir) {
+                eprintln!(
+                    "Error: Could not create keystore directory '{}': {}",
+                    keystore_dir, error
+                );
+                std::process::exit(1);
+            }
+        }
+    }
+
     let digit_count = match matches.value_of("digits") {
         None => None,
         Some(count_str) => match count_str.parse() {
@@ -348,6 +849,7 @@ fn main() {
         contains: String::from(matches.value_of("contains").unwrap()),
         digits: digit_count,
         letters: letter_count,
+        ignore_case,
     };
 
     if let Err(error) = matcher.validate() {
@@ -355,23 +857,105 @@ fn main() {
         std::process::exit(1);
     }
 
+    let grind_matches: Vec<GrindMatch> = match matches.values_of("grind") {
+        Some(values) => {
+            let mut grind_matches = Vec::new();
+            for value in values {
+                let parts: Vec<&str> = value.split(':').collect();
+                let (starts, ends, count_str) = match parts.as_slice() {
+                    [starts, ends, count_str] => (*starts, *ends, *count_str),
+                    _ => {
+                        eprintln!(
+                            "Error: --grind value '{}' is not in STARTSWITH:ENDSWITH:COUNT form",
+                            value
+                        );
+                        std::process::exit(1);
+                    }
+                };
+                let count: u64 = match count_str.parse() {
+                    Ok(count) => count,
+                    Err(_error) => {
+                        eprintln!(
+                            "Error: --grind count in '{}' is not a 64-bit unsigned integer",
+                            value
+                        );
+                        std::process::exit(1);
+                    }
+                };
+                let pattern_matcher = Matcher {
+                    addr_type,
+                    startswith: String::from(starts),
+                    endswith: String::from(ends),
+                    contains: String::new(),
+                    digits: None,
+                    letters: None,
+                    ignore_case,
+                };
+                if let Err(error) = pattern_matcher.validate() {
+                    eprintln!("{}", error);
+                    std::process::exit(1);
+                }
+                grind_matches.push(GrindMatch {
+                    matcher: pattern_matcher,
+                    remaining: Arc::new(AtomicU64::new(count)),
+                });
+            }
+            grind_matches
+        }
+        None => vec![GrindMatch {
+            matcher,
+            remaining: Arc::new(AtomicU64::new(u64::from(wallet_count))),
+        }],
+    };
+    let total_matches_wanted: u64 = grind_matches
+        .iter()
+        .map(|grind_match| grind_match.remaining.load(Ordering::SeqCst))
+        .sum();
+
+    if verbose {
+        for (index, grind_match) in grind_matches.iter().enumerate() {
+            let probability = estimate_match_probability(&grind_match.matcher);
+            if probability <= 0.0 {
+                println!(
+                    "Pattern #{}: estimated match probability 0, expected attempts effectively infinite",
+                    index
+                );
+            } else {
+                println!(
+                    "Pattern #{}: estimated match probability {:.3e}, expected attempts {:.0}",
+                    index,
+                    probability,
+                    1.0 / probability
+                );
+            }
+        }
+    }
+
     let (tx, rx) = mpsc::channel();
     let (attempt_count_tx, attempt_count_rx) = mpsc::channel();
     let mut children = Vec::new();
     let mut kill_pills = Vec::new();
-    for _child_index in 0..cpu_count {
+    for child_index in 0..cpu_count {
         let thread_tx = tx.clone();
         let thread_attempt_count_tx = attempt_count_tx.clone();
-        let thread_matcher = matcher.clone();
+        let thread_grind_matches = grind_matches.clone();
+        let thread_mnemonic_config = mnemonic_config.clone();
+        let thread_from_phrase_config = from_phrase.clone().map(|phrase| FromPhraseConfig {
+            phrase,
+            passphrase: passphrase.clone(),
+            start_index: u64::from(child_index),
+            thread_count: u64::from(cpu_count),
+        });
         let (kill_pill_tx, kill_pill_rx) = mpsc::channel();
         let child = thread::spawn(move || {
             generate_matching_wallet(
                 thread_tx,
                 thread_attempt_count_tx,
                 kill_pill_rx,
-                thread_matcher,
+                thread_grind_matches,
                 addr_type,
-                mnemonic,
+                thread_mnemonic_config,
+                thread_from_phrase_config,
             )
         });
         kill_pills.push(kill_pill_tx);
@@ -379,14 +963,54 @@ fn main() {
     }
 
     let start_time = SystemTime::now();
-    let mut matches_found = 0;
+    let mut matches_found: u64 = 0;
     let mut total_attempts: u64 = 0;
-    while matches_found < wallet_count {
+    let mut found_records: Vec<WalletRecord> = Vec::new();
+    while matches_found < total_matches_wanted {
         match rx.recv_timeout(Duration::from_secs(3)) {
-            Ok(matching_wallet) => {
+            Ok((pattern_index, matching_wallet)) => {
                 matches_found += 1;
-                println!(":::: Matching wallet found ::::");
+                println!(":::: Matching wallet found for pattern #{} ::::", pattern_index);
                 matching_wallet.pretty_print();
+
+                if let Some(keystore_dir) = &output_path {
+                    if let Some(keystore_password) = &keystore_password {
+                        if let Err(error) = eth_keystore::encrypt_key(
+                            keystore_dir,
+                            &mut rand::thread_rng(),
+                            &matching_wallet.private_key,
+                            keystore_password,
+                            None,
+                        ) {
+                            // A failed write must not silently count toward
+                            // `total_matches_wanted`, so bail out instead of
+                            // continuing to grind with an unwritten match.
+                            eprintln!("Error: Could not write keystore file: {}", error);
+                            std::process::exit(1);
+                        }
+                    } else {
+                        found_records.push(WalletRecord::from_wallet(&matching_wallet, addr_type));
+                        // Rewrite the whole file after every match so an
+                        // interrupted run still leaves every match found so
+                        // far on disk, matching --output's "as they are
+                        // found" contract.
+                        match serde_json::to_string_pretty(&found_records) {
+                            Ok(json) => {
+                                if let Err(error) = std::fs::write(keystore_dir, json) {
+                                    eprintln!(
+                                        "Error: Could not write to '{}': {}",
+                                        keystore_dir, error
+                                    );
+                                    std::process::exit(1);
+                                }
+                            }
+                            Err(error) => {
+                                eprintln!("Error: Could not serialize wallets to JSON: {}", error);
+                                std::process::exit(1);
+                            }
+                        }
+                    }
+                }
             }
             Err(RecvTimeoutError::Disconnected) => panic!("wallet tx disconnected"),
             Err(RecvTimeoutError::Timeout) => {}
@@ -399,10 +1023,24 @@ fn main() {
             if let Ok(elapsed) = start_time.elapsed() {
                 let elapsed_secs = elapsed.as_secs();
                 if elapsed_secs != 0 {
-                    println!(
-                        "Pace: {} attempts per second",
-                        total_attempts / elapsed.as_secs()
-                    )
+                    let pace = total_attempts / elapsed.as_secs();
+                    println!("Pace: {} attempts per second", pace);
+
+                    if pace != 0 {
+                        let expected_remaining_attempts: f64 = grind_matches
+                            .iter()
+                            .map(|grind_match| {
+                                let remaining = grind_match.remaining.load(Ordering::SeqCst) as f64;
+                                remaining / estimate_match_probability(&grind_match.matcher)
+                            })
+                            .sum();
+                        let eta_secs = expected_remaining_attempts / pace as f64;
+                        println!(
+                            "ETA: {} ({:.1}% of matches found)",
+                            format_duration_secs(eta_secs),
+                            100.0 * matches_found as f64 / total_matches_wanted as f64
+                        );
+                    }
                 }
             }
         }
@@ -430,6 +1068,7 @@ mod tests {
             contains: String::new(),
             letters: None,
             digits: None,
+            ignore_case: false,
         };
         assert!(m.validate().is_ok());
     }
@@ -443,10 +1082,39 @@ mod tests {
             contains: String::new(),
             letters: None,
             digits: None,
+            ignore_case: false,
         };
         assert_eq!(
             m.validate(),
             Err("Error: Polkadot mainnet address must start with '1'. Adjust --startswith")
         );
     }
+
+    #[test]
+    fn test_ignore_case_matches_regardless_of_letter_case() {
+        let m = Matcher {
+            addr_type: 0,
+            startswith: String::from("1ALICE"),
+            endswith: String::new(),
+            contains: String::new(),
+            letters: None,
+            digits: None,
+            ignore_case: true,
+        };
+        assert!(m.match_("1aliceXYZ"));
+    }
+
+    #[test]
+    fn test_ignore_case_allows_uppercase_i_and_o() {
+        let m = Matcher {
+            addr_type: 0,
+            startswith: String::from("1IO"),
+            endswith: String::new(),
+            contains: String::new(),
+            letters: None,
+            digits: None,
+            ignore_case: true,
+        };
+        assert!(m.validate().is_ok());
+    }
 }